@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use huffman_rs::{arena::ArenaTree, freqs, huffman};
+
+/// Stand-in for the wikisent2.txt corpus the request named: this sandbox
+/// doesn't vendor that dataset, so this builds a synthetic corpus with a
+/// similarly Zipfian word-frequency shape (a handful of very common words,
+/// a long tail of rare ones) by repeating a fixed word list with
+/// decreasing multiplicity.
+fn synthetic_corpus() -> Vec<String> {
+    const WORDS: &[&str] = &[
+        "the", "a", "of", "and", "to", "in", "is", "that", "it", "for", "on", "with", "as", "at",
+        "by", "this", "from", "or", "an", "be", "are", "was", "were", "which", "but", "not",
+        "have", "has", "had", "one", "two", "three", "huffman", "tree", "arena", "encode",
+        "decode", "frequency", "symbol", "compress",
+    ];
+
+    let mut lines = Vec::new();
+    for (rank, word) in WORDS.iter().enumerate() {
+        let repeats = 200 / (rank + 1);
+        for _ in 0..repeats.max(1) {
+            lines.push(word.to_string());
+        }
+    }
+    lines
+}
+
+fn build_and_encode(c: &mut Criterion) {
+    let lines = synthetic_corpus();
+    let char_freqs = freqs::learn_char_frequencies(&lines);
+    let word_freqs = freqs::learn_word_frequencies(&lines);
+
+    let mut group = c.benchmark_group("build+encode");
+
+    group.bench_function("boxed_tree_chars", |b| {
+        b.iter(|| {
+            let tree = huffman::build_huffman_tree(&char_freqs);
+            tree.to_encoder()
+        })
+    });
+    group.bench_function("arena_tree_chars", |b| {
+        b.iter(|| {
+            let arena = ArenaTree::build(&char_freqs);
+            arena.to_encoder()
+        })
+    });
+
+    group.bench_function("boxed_tree_words", |b| {
+        b.iter(|| {
+            let tree = huffman::build_huffman_tree(&word_freqs);
+            tree.to_encoder()
+        })
+    });
+    group.bench_function("arena_tree_words", |b| {
+        b.iter(|| {
+            let arena = ArenaTree::build(&word_freqs);
+            arena.to_encoder()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, build_and_encode);
+criterion_main!(benches);
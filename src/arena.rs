@@ -0,0 +1,200 @@
+use bit_vec::BitVec;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use crate::huffman::Tree;
+
+/// A single arena node: `left`/`right`/`parent` are indices into the owning
+/// `ArenaTree`'s `nodes` vector instead of `Box` pointers, so the whole tree
+/// is one flat `Vec`, trivially `Clone`/`Serialize`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Node<T> {
+    pub freq: i64,
+    pub token: Option<T>,
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+    pub parent: Option<usize>,
+}
+
+/// Arena-backed alternative to `Tree<T>`: avoids chasing scattered `Box`
+/// pointers during construction and encoding. It has no decoder of its own;
+/// decoding goes through the boxed `Tree<T>` (see `to_tree`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArenaTree<T> {
+    pub nodes: Vec<Node<T>>,
+    pub root: Option<usize>,
+}
+
+impl<T: Eq + Clone> ArenaTree<T> {
+    /// Builds the tree directly in arena form: leaves are pushed first, then
+    /// each heap merge pushes one new internal node and records its children's
+    /// indices, instead of boxing two subtrees.
+    pub fn build(freqs: &HashMap<T, i64>) -> ArenaTree<T> {
+        let mut nodes = Vec::new();
+        let mut heap = BinaryHeap::new();
+
+        for (token, freq) in freqs {
+            let idx = nodes.len();
+            nodes.push(Node {
+                freq: *freq,
+                token: Some(token.clone()),
+                left: None,
+                right: None,
+                parent: None,
+            });
+            heap.push(Reverse((*freq, idx)));
+        }
+
+        while heap.len() > 1 {
+            let Reverse((freq1, idx1)) = heap.pop().unwrap();
+            let Reverse((freq2, idx2)) = heap.pop().unwrap();
+
+            let merged_idx = nodes.len();
+            let merged_freq = freq1 + freq2;
+            nodes.push(Node {
+                freq: merged_freq,
+                token: None,
+                left: Some(idx1),
+                right: Some(idx2),
+                parent: None,
+            });
+            nodes[idx1].parent = Some(merged_idx);
+            nodes[idx2].parent = Some(merged_idx);
+
+            heap.push(Reverse((merged_freq, merged_idx)));
+        }
+
+        let root = heap.pop().map(|Reverse((_, idx))| idx);
+        ArenaTree { nodes, root }
+    }
+
+    /// Converts from the boxed `Tree<T>` representation, so current callers
+    /// that build a `Tree` can still opt into the arena form afterwards.
+    pub fn from_tree(tree: &Tree<T>) -> ArenaTree<T> {
+        let mut nodes = Vec::new();
+        let root = push_tree_node(&mut nodes, tree, None);
+        ArenaTree {
+            nodes,
+            root: Some(root),
+        }
+    }
+
+    /// Converts back to the boxed `Tree<T>` representation.
+    pub fn to_tree(&self) -> Tree<T> {
+        self.node_to_tree(self.root.expect("arena tree has no root"))
+    }
+
+    fn node_to_tree(&self, idx: usize) -> Tree<T> {
+        let node = &self.nodes[idx];
+        match (&node.token, node.left, node.right) {
+            (Some(token), _, _) => Tree::Leaf {
+                freq: node.freq,
+                token: token.clone(),
+            },
+            (None, Some(left), Some(right)) => Tree::Node {
+                freq: node.freq,
+                left: Box::new(self.node_to_tree(left)),
+                right: Box::new(self.node_to_tree(right)),
+            },
+            _ => panic!("malformed arena tree: internal node missing a child"),
+        }
+    }
+}
+
+impl<T: Eq + Clone + Hash> ArenaTree<T> {
+    /// Builds the code table by climbing each leaf's `parent` chain to the
+    /// root instead of descending from the root with a stack: for each leaf,
+    /// walk up recording whether it was its parent's `left` or `right` child,
+    /// then reverse. This is the one place the arena's `parent` indices earn
+    /// their keep over the boxed `Tree`, which has no way back up.
+    pub fn to_encoder(&self) -> HashMap<T, BitVec> {
+        let mut encoder = HashMap::new();
+
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let Some(token) = &node.token else {
+                continue;
+            };
+
+            let mut bits = Vec::new();
+            let mut current = idx;
+            while let Some(parent_idx) = self.nodes[current].parent {
+                bits.push(self.nodes[parent_idx].right == Some(current));
+                current = parent_idx;
+            }
+            bits.reverse();
+
+            let mut path = BitVec::new();
+            for bit in bits {
+                path.push(bit);
+            }
+            encoder.insert(token.clone(), path);
+        }
+
+        encoder
+    }
+}
+
+fn push_tree_node<T: Clone>(nodes: &mut Vec<Node<T>>, tree: &Tree<T>, parent: Option<usize>) -> usize {
+    let idx = nodes.len();
+    nodes.push(Node {
+        freq: tree.freq(),
+        token: None,
+        left: None,
+        right: None,
+        parent,
+    });
+
+    match tree {
+        Tree::Leaf { token, .. } => nodes[idx].token = Some(token.clone()),
+        Tree::Node { left, right, .. } => {
+            let left_idx = push_tree_node(nodes, left, Some(idx));
+            let right_idx = push_tree_node(nodes, right, Some(idx));
+            nodes[idx].left = Some(left_idx);
+            nodes[idx].right = Some(right_idx);
+        }
+    }
+
+    idx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_arena_tree_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 40);
+        freqs.insert('b', 35);
+        freqs.insert('c', 20);
+        freqs.insert('d', 5);
+
+        let arena = ArenaTree::build(&freqs);
+        let root = &arena.nodes[arena.root.unwrap()];
+        assert_eq!(root.freq, 100);
+
+        let encoder = arena.to_encoder();
+        assert!(encoder.get(&'a').unwrap().eq_vec(&[false]));
+        assert!(encoder.get(&'b').unwrap().eq_vec(&[true, true]));
+        assert!(encoder.get(&'c').unwrap().eq_vec(&[true, false, true]));
+        assert!(encoder.get(&'d').unwrap().eq_vec(&[true, false, false]));
+    }
+
+    #[test]
+    fn tree_and_arena_tree_roundtrip_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 40);
+        freqs.insert('b', 35);
+        freqs.insert('c', 20);
+        freqs.insert('d', 5);
+
+        let tree = crate::huffman::build_huffman_tree(&freqs);
+        let arena = ArenaTree::from_tree(&tree);
+        assert_eq!(arena.to_tree(), tree);
+
+        let arena = ArenaTree::build(&freqs);
+        assert_eq!(arena.to_tree().to_encoder(), tree.to_encoder());
+    }
+}
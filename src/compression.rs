@@ -1,14 +1,7 @@
 use bit_vec::BitVec;
 use rayon::prelude::*;
-use rmp_serde::{self, encode};
 use serde::{Deserialize, Serialize};
-use std::{
-    collections::HashMap,
-    fs::{self, File},
-    hash::Hash,
-    io::Write,
-    path::Path,
-};
+use std::{collections::HashMap, hash::Hash};
 
 use crate::{
     freqs,
@@ -20,6 +13,56 @@ use Tree::*;
 struct CompressedData<T: Eq + Hash> {
     encoder: HashMap<T, BitVec>,
     data: Vec<BitVec>,
+    // per-line symbol count: a single-symbol alphabet gets a zero-length code,
+    // so `data`'s bit length alone can't tell decoding how many symbols a line held
+    symbol_counts: Vec<u64>,
+}
+
+/// Error returned by `Tree::to_packed_encoder` when a code doesn't fit in a
+/// `u64`; callers should fall back to the `BitVec`-based `to_encoder` instead.
+#[derive(Debug)]
+pub struct CodeTooLongError {
+    pub length: usize,
+}
+
+impl std::fmt::Display for CodeTooLongError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "code length {} does not fit in the 64-bit packed representation",
+            self.length
+        )
+    }
+}
+
+impl std::error::Error for CodeTooLongError {}
+
+/// Encodes a stream of tokens with a packed code table, shifting each code's
+/// bits directly into the output `BitVec` instead of cloning and extending by
+/// a per-symbol `BitVec` from the table.
+fn pack_tokens<T: Eq + Hash, I: Iterator<Item = T>>(
+    tokens: I,
+    packed: &HashMap<T, (u64, u32)>,
+) -> BitVec {
+    let mut bits = BitVec::new();
+    for token in tokens {
+        let (value, len) = *packed.get(&token).unwrap();
+        for bit in (0..len).rev() {
+            bits.push((value >> bit) & 1 == 1);
+        }
+    }
+    bits
+}
+
+/// Compact on-disk counterpart to `CompressedData`: instead of the full
+/// `HashMap<T, BitVec>` encoder, stores only the sorted `(symbol, code_length)`
+/// pairs, since canonical codes can be replayed from the lengths alone.
+#[derive(Serialize, Deserialize)]
+struct CanonicalCompressedData<T: Eq + Hash> {
+    code_lengths: Vec<(T, u8)>,
+    data: Vec<BitVec>,
+    // see `CompressedData::symbol_counts`
+    symbol_counts: Vec<u64>,
 }
 
 pub fn compress_as_chars(lines: &Vec<String>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
@@ -27,19 +70,62 @@ pub fn compress_as_chars(lines: &Vec<String>) -> Result<Vec<u8>, Box<dyn std::er
     let tree = huffman::build_huffman_tree(&freqs);
     let encoder = tree.to_encoder();
 
-    let data: Vec<_> = lines
+    let packed = tree.to_packed_encoder().ok();
+    let (data, symbol_counts): (Vec<_>, Vec<_>) = lines
         .par_iter()
         .map(|line| {
-            line.chars()
+            let bits = match &packed {
+                Some(packed) => pack_tokens(line.chars(), packed),
+                None => line
+                    .chars()
+                    .map(|ch| encoder.get(&ch).unwrap().clone())
+                    .fold(BitVec::new(), |mut vec1, vec2| {
+                        vec1.extend(vec2);
+                        vec1
+                    }),
+            };
+            (bits, line.chars().count() as u64)
+        })
+        .unzip();
+
+    let compressed_data = CompressedData {
+        encoder,
+        data,
+        symbol_counts,
+    };
+    rmp_serde::encode::to_vec(&compressed_data).map_err(|err| err.into())
+}
+
+/// Arena-backed variant of [`compress_as_chars`]: builds the code table via
+/// `arena::ArenaTree` (one flat `Vec<Node>`, no `Box` chasing) instead of
+/// `huffman::build_huffman_tree`'s boxed `Tree`. Produces the same on-disk
+/// `CompressedData` format, so it's a drop-in alternative for callers who
+/// want to try the arena representation -- `decompress` doesn't care which
+/// one built the encoder.
+pub fn compress_as_chars_arena(lines: &Vec<String>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let freqs = freqs::learn_char_frequencies(lines);
+    let arena = crate::arena::ArenaTree::build(&freqs);
+    let encoder = arena.to_encoder();
+
+    let (data, symbol_counts): (Vec<_>, Vec<_>) = lines
+        .par_iter()
+        .map(|line| {
+            let bits = line
+                .chars()
                 .map(|ch| encoder.get(&ch).unwrap().clone())
                 .fold(BitVec::new(), |mut vec1, vec2| {
                     vec1.extend(vec2);
                     vec1
-                })
+                });
+            (bits, line.chars().count() as u64)
         })
-        .collect();
+        .unzip();
 
-    let compressed_data = CompressedData { encoder, data };
+    let compressed_data = CompressedData {
+        encoder,
+        data,
+        symbol_counts,
+    };
     rmp_serde::encode::to_vec(&compressed_data).map_err(|err| err.into())
 }
 
@@ -48,22 +134,189 @@ pub fn compress_as_words(lines: &Vec<String>) -> Result<Vec<u8>, Box<dyn std::er
     let tree = huffman::build_huffman_tree(&freqs);
     let encoder = tree.to_encoder();
 
-    let data: Vec<_> = lines
+    let packed = tree.to_packed_encoder().ok();
+    let (data, symbol_counts): (Vec<_>, Vec<_>) = lines
+        .par_iter()
+        .map(|line| {
+            let bits = match &packed {
+                Some(packed) => {
+                    pack_tokens(line.split_ascii_whitespace().map(|s| s.to_string()), packed)
+                }
+                None => line
+                    .split_ascii_whitespace()
+                    .map(|s| encoder.get(s).unwrap().clone())
+                    .fold(BitVec::new(), |mut vec1, vec2| {
+                        vec1.extend(vec2);
+                        vec1
+                    }),
+            };
+            (bits, line.split_ascii_whitespace().count() as u64)
+        })
+        .unzip();
+
+    let compressed_data = CompressedData {
+        encoder,
+        data,
+        symbol_counts,
+    };
+    rmp_serde::encode::to_vec(&compressed_data).map_err(|err| err.into())
+}
+
+/// Canonical-Huffman variant of [`compress_as_chars`]. Stores only per-symbol
+/// code lengths in the header instead of the full `BitVec` encoder, which is
+/// much smaller when the alphabet is large (e.g. the word-frequency path).
+pub fn compress_as_chars_canonical(
+    lines: &Vec<String>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let freqs = freqs::learn_char_frequencies(lines);
+    let tree = huffman::build_huffman_tree(&freqs);
+    let (code_lengths, encoder) = sorted_code_lengths_and_encoder(&tree);
+
+    let (data, symbol_counts): (Vec<_>, Vec<_>) = lines
         .par_iter()
         .map(|line| {
-            line.split_ascii_whitespace()
+            let bits = line
+                .chars()
+                .map(|ch| encoder.get(&ch).unwrap().clone())
+                .fold(BitVec::new(), |mut vec1, vec2| {
+                    vec1.extend(vec2);
+                    vec1
+                });
+            (bits, line.chars().count() as u64)
+        })
+        .unzip();
+
+    let compressed_data = CanonicalCompressedData {
+        code_lengths,
+        data,
+        symbol_counts,
+    };
+    rmp_serde::encode::to_vec(&compressed_data).map_err(|err| err.into())
+}
+
+/// Canonical-Huffman variant of [`compress_as_words`]. See
+/// [`compress_as_chars_canonical`] for why this shrinks the header.
+pub fn compress_as_words_canonical(
+    lines: &Vec<String>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let freqs = freqs::learn_word_frequencies(lines);
+    let tree = huffman::build_huffman_tree(&freqs);
+    let (code_lengths, encoder) = sorted_code_lengths_and_encoder(&tree);
+
+    let (data, symbol_counts): (Vec<_>, Vec<_>) = lines
+        .par_iter()
+        .map(|line| {
+            let bits = line
+                .split_ascii_whitespace()
                 .map(|s| encoder.get(s).unwrap().clone())
                 .fold(BitVec::new(), |mut vec1, vec2| {
                     vec1.extend(vec2);
                     vec1
-                })
+                });
+            (bits, line.split_ascii_whitespace().count() as u64)
         })
-        .collect();
+        .unzip();
 
-    let compressed_data = CompressedData { encoder, data };
+    let compressed_data = CanonicalCompressedData {
+        code_lengths,
+        data,
+        symbol_counts,
+    };
     rmp_serde::encode::to_vec(&compressed_data).map_err(|err| err.into())
 }
 
+/// Sorted `(symbol, code_length)` pairs alongside the `BitVec` encoder they
+/// were replayed into, as returned by `sorted_code_lengths_and_encoder`.
+type CodeLengthsAndEncoder<T> = (Vec<(T, u8)>, HashMap<T, BitVec>);
+
+fn sorted_code_lengths_and_encoder<T: Eq + Clone + Hash + Ord>(
+    tree: &Tree<T>,
+) -> CodeLengthsAndEncoder<T> {
+    let mut code_lengths: Vec<_> = tree.code_lengths().into_iter().collect();
+    code_lengths.sort_by(|(token1, len1), (token2, len2)| (len1, token1).cmp(&(len2, token2)));
+
+    let encoder = canonical_encoder_from_sorted_lengths(&code_lengths);
+    (code_lengths, encoder)
+}
+
+/// Increments a bit vector treated as a binary number (MSB first) by one,
+/// rippling the carry from the last bit towards the first. Growing the
+/// canonical code by one symbol never needs this to overflow past the first
+/// bit for a valid set of Huffman code lengths, but it's handled anyway
+/// rather than assumed away.
+fn increment_code(bits: &mut Vec<bool>) {
+    for bit in bits.iter_mut().rev() {
+        if !*bit {
+            *bit = true;
+            return;
+        }
+        *bit = false;
+    }
+    bits.insert(0, true);
+}
+
+/// Replays the canonical code assignment from a `(symbol, code_length)`
+/// header: the first (shortest) symbol gets code 0, and each subsequent
+/// symbol's code is `(prev_code + 1) << (len_curr - len_prev)`. `lengths` must
+/// already be sorted by `(code_length, symbol)`, which is what both sides sort
+/// by, so the codes line up without shipping any of them over the wire.
+///
+/// The code is carried as a plain bit vector rather than a fixed-width
+/// integer, so -- unlike `Tree::to_packed_encoder`'s `u64` codes -- there's no
+/// length ceiling here: canonical assignment is pure length-driven
+/// bookkeeping, and the large, skewed symbol tables the word-frequency path
+/// produces are exactly where codes longer than 64 bits arise.
+fn canonical_encoder_from_sorted_lengths<T: Eq + Clone + Hash>(
+    lengths: &[(T, u8)],
+) -> HashMap<T, BitVec> {
+    let mut encoder = HashMap::new();
+    if lengths.is_empty() {
+        return encoder;
+    }
+
+    let mut bits = vec![false; lengths[0].1 as usize];
+    let mut prev_len = lengths[0].1;
+    for (i, (token, len)) in lengths.iter().enumerate() {
+        if i > 0 {
+            increment_code(&mut bits);
+            bits.extend(std::iter::repeat_n(false, (len - prev_len) as usize));
+        }
+
+        let code = bits.iter().copied().collect();
+        encoder.insert(token.clone(), code);
+
+        prev_len = *len;
+    }
+
+    encoder
+}
+
+pub fn decompress_canonical<T, F>(
+    data: Vec<u8>,
+    tokens_to_line: F,
+) -> Result<Vec<String>, Box<dyn std::error::Error>>
+where
+    T: Clone + Eq + Hash + Ord + Send + Sync + for<'a> Deserialize<'a>,
+    F: Fn(Vec<T>) -> String + Send + Sync,
+{
+    let CanonicalCompressedData {
+        code_lengths,
+        data,
+        symbol_counts,
+    }: CanonicalCompressedData<T> = rmp_serde::decode::from_slice(&data[..])?;
+
+    let encoder = canonical_encoder_from_sorted_lengths(&code_lengths);
+    let tree = Tree::from_code_table(&encoder);
+
+    let lines: Vec<_> = data
+        .par_iter()
+        .zip(symbol_counts.par_iter())
+        .map(|(line, &count)| tokens_to_line(tree.decode_bits_limited(line, count as usize)))
+        .collect();
+
+    Ok(lines)
+}
+
 pub fn decompress<T, F>(
     data: Vec<u8>,
     tokens_to_line: F,
@@ -72,44 +325,46 @@ where
     T: Clone + Eq + Hash + Send + Sync + for<'a> Deserialize<'a>,
     F: Fn(Vec<T>) -> String + Send + Sync,
 {
-    let CompressedData { encoder, data }: CompressedData<T> =
-        rmp_serde::decode::from_slice(&data[..])?;
+    let CompressedData {
+        encoder,
+        data,
+        symbol_counts,
+    }: CompressedData<T> = rmp_serde::decode::from_slice(&data[..])?;
 
-    // TODO: extract into separate fun
-    let mut decoder = HashMap::new();
-    for (token, prefix) in encoder.clone() {
-        decoder.insert(prefix, token);
-    }
+    let tree = Tree::from_code_table(&encoder);
 
     let lines: Vec<_> = data
         .par_iter()
-        .map(|line| {
-            let mut pos = 0;
-            let mut candidate = BitVec::new();
-            let mut tokens = vec![];
-
-            while pos < line.len() {
-                let bit = line.get(pos).unwrap();
-                candidate.push(bit);
-                pos += 1;
-
-                match decoder.get(&candidate) {
-                    Some(token) => {
-                        tokens.push(token.clone());
-
-                        candidate = BitVec::new();
-                    }
-                    None => (),
-                }
-            }
-            tokens_to_line(tokens)
-        })
+        .zip(symbol_counts.par_iter())
+        .map(|(line, &count)| tokens_to_line(tree.decode_bits_limited(line, count as usize)))
         .collect();
 
     Ok(lines)
 }
 
 impl<T: Eq + Clone + Hash> Tree<T> {
+    /// Packs each code into a right-aligned `u64` value plus its bit length,
+    /// instead of a heap-allocated `BitVec`, so encoding can shift bits into a
+    /// running buffer rather than cloning and extending a `BitVec` per symbol.
+    /// Errors if any code is longer than 64 bits, which can happen with highly
+    /// skewed word-frequency distributions; callers should fall back to the
+    /// `BitVec`-based `to_encoder`.
+    pub fn to_packed_encoder(&self) -> Result<HashMap<T, (u64, u32)>, CodeTooLongError> {
+        let mut packed = HashMap::new();
+        for (token, bits) in self.to_encoder() {
+            if bits.len() > 64 {
+                return Err(CodeTooLongError { length: bits.len() });
+            }
+
+            let mut value: u64 = 0;
+            for bit in &bits {
+                value = (value << 1) | bit as u64;
+            }
+            packed.insert(token, (value, bits.len() as u32));
+        }
+        Ok(packed)
+    }
+
     // TODO: pass pre-computed encoder if possible
     pub fn encode(&self, data: &[T]) -> Vec<BitVec> {
         let encoder = self.to_encoder();
@@ -127,7 +382,7 @@ impl<T: Eq + Clone + Hash> Tree<T> {
 
         let mut res = vec![];
         for code in data {
-            res.push(decoder.get(&code).unwrap().clone());
+            res.push(decoder.get(code).unwrap().clone());
         }
         res
     }
@@ -136,8 +391,7 @@ impl<T: Eq + Clone + Hash> Tree<T> {
         let mut encoder = HashMap::new();
 
         let mut stack = vec![(self, BitVec::new())];
-        while !stack.is_empty() {
-            let (node, path) = stack.pop().unwrap();
+        while let Some((node, path)) = stack.pop() {
             match node {
                 Leaf { token, .. } => {
                     encoder.insert(token.clone(), path.clone());
@@ -158,9 +412,7 @@ impl<T: Eq + Clone + Hash> Tree<T> {
     }
 
     pub fn to_decoder(&self, encoder: Option<&HashMap<T, BitVec>>) -> HashMap<BitVec, T> {
-        let encoder = encoder
-            .map(|m| m.clone())
-            .unwrap_or_else(|| self.to_encoder());
+        let encoder = encoder.cloned().unwrap_or_else(|| self.to_encoder());
 
         let mut decoder = HashMap::new();
         for (token, prefix) in encoder.clone() {
@@ -222,4 +474,295 @@ mod tests {
         let res_lines = decompress(data, |x: Vec<String>| x.join(" ")).unwrap();
         assert_eq!(&lines, &res_lines);
     }
+
+    #[test]
+    fn compress_decompress_canonical_test() {
+        let lines = vec![
+            "hey there! nice to meet you.".to_string(),
+            "Serde is a framework for serializing and deserializing Rust data structures"
+                .to_string(),
+        ];
+
+        let data = compress_as_chars_canonical(&lines).unwrap();
+        let res_lines = decompress_canonical(data, |x: Vec<char>| x.into_iter().collect()).unwrap();
+        assert_eq!(&lines, &res_lines);
+
+        let data = compress_as_words_canonical(&lines).unwrap();
+        let res_lines = decompress_canonical(data, |x: Vec<String>| x.join(" ")).unwrap();
+        assert_eq!(&lines, &res_lines);
+    }
+
+    #[test]
+    fn compress_decompress_single_symbol_alphabet_test() {
+        // a single-symbol alphabet gets a zero-length code, so a naive decoder
+        // that infers symbol count from bit length alone loses all content
+        let lines = vec!["aaaa".to_string(), "aaaaaaaaa".to_string()];
+
+        let data = compress_as_chars(&lines).unwrap();
+        let res_lines = decompress(data, |x: Vec<char>| x.into_iter().collect()).unwrap();
+        assert_eq!(&lines, &res_lines);
+
+        let data = compress_as_chars_canonical(&lines).unwrap();
+        let res_lines = decompress_canonical(data, |x: Vec<char>| x.into_iter().collect()).unwrap();
+        assert_eq!(&lines, &res_lines);
+    }
+
+    #[test]
+    fn compress_decompress_arena_test() {
+        let lines = vec![
+            "hey there! nice to meet you.".to_string(),
+            "Serde is a framework for serializing and deserializing Rust data structures"
+                .to_string(),
+        ];
+
+        let data = compress_as_chars_arena(&lines).unwrap();
+        let res_lines = decompress(data, |x: Vec<char>| x.into_iter().collect()).unwrap();
+        assert_eq!(&lines, &res_lines);
+    }
+
+    #[test]
+    fn arena_encoder_matches_boxed_tree_weighted_length_test() {
+        // the arena and boxed trees can assign different code *lengths* to
+        // individual tied-frequency symbols (heap tie-breaking isn't
+        // identical), but both run the same greedy merge over the same
+        // frequencies, so the total weighted code length -- the actual
+        // quantity Huffman coding minimizes -- must be identical. This is a
+        // correctness check; see `benches/arena_vs_boxed.rs` for the
+        // build+encode timing comparison between the two representations.
+        let lines = vec![
+            "hey there! nice to meet you.".to_string(),
+            "Serde is a framework for serializing and deserializing Rust data structures"
+                .to_string(),
+        ];
+
+        let freqs = freqs::learn_char_frequencies(&lines);
+        let boxed_encoder = build_huffman_tree(&freqs).to_encoder();
+        let arena_encoder = crate::arena::ArenaTree::build(&freqs).to_encoder();
+
+        assert_eq!(boxed_encoder.len(), arena_encoder.len());
+
+        let weighted_length = |encoder: &HashMap<char, BitVec>| -> i64 {
+            encoder
+                .iter()
+                .map(|(token, bits)| freqs[token] * bits.len() as i64)
+                .sum()
+        };
+        assert_eq!(weighted_length(&boxed_encoder), weighted_length(&arena_encoder));
+    }
+
+    #[test]
+    fn to_packed_encoder_matches_to_encoder_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 40);
+        freqs.insert('b', 35);
+        freqs.insert('c', 20);
+        freqs.insert('d', 5);
+
+        let tree = build_huffman_tree(&freqs);
+        let encoder = tree.to_encoder();
+        let packed = tree.to_packed_encoder().unwrap();
+
+        for (token, bits) in &encoder {
+            let (value, len) = packed[token];
+            assert_eq!(len as usize, bits.len());
+
+            let mut expected = 0u64;
+            for bit in bits {
+                expected = (expected << 1) | bit as u64;
+            }
+            assert_eq!(value, expected);
+        }
+
+        let tokens = ['a', 'a', 'b', 'd', 'c', 'a'];
+        let packed_bits = pack_tokens(tokens.iter().copied(), &packed);
+        let unpacked_bits = tokens
+            .iter()
+            .map(|ch| encoder.get(ch).unwrap().clone())
+            .fold(BitVec::new(), |mut acc, code| {
+                acc.extend(code);
+                acc
+            });
+        assert_eq!(packed_bits, unpacked_bits);
+    }
+
+    #[test]
+    fn canonical_encoder_matches_code_lengths_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 40);
+        freqs.insert('b', 35);
+        freqs.insert('c', 20);
+        freqs.insert('d', 5);
+
+        let tree = build_huffman_tree(&freqs);
+        let (code_lengths, canonical_encoder) = sorted_code_lengths_and_encoder(&tree);
+
+        // canonical codes preserve the original lengths, even though the bit
+        // patterns themselves differ from the tree-shape-derived encoder
+        let lengths: HashMap<_, _> = code_lengths.into_iter().collect();
+        for (token, code) in &canonical_encoder {
+            assert_eq!(code.len() as u8, lengths[token]);
+        }
+    }
+
+    #[test]
+    fn canonical_encoder_handles_codes_over_64_bits_test() {
+        // unlike the packed `u64` path, canonical assignment has no width
+        // ceiling: a length of 65 bits must produce a 65-bit code, not error
+        let lengths = vec![('a', 1u8), ('b', 65u8)];
+        let encoder = canonical_encoder_from_sorted_lengths(&lengths);
+        assert_eq!(encoder[&'a'].len(), 1);
+        assert_eq!(encoder[&'b'].len(), 65);
+
+        // 'a' is the shortest code (0), so 'b' is (0 + 1) << 64, i.e. a single
+        // 1 bit followed by 64 zero bits
+        let b_bits: Vec<bool> = encoder[&'b'].iter().collect();
+        assert!(b_bits[0]);
+        assert!(b_bits[1..].iter().all(|&bit| !bit));
+    }
+}
+
+/// Property-based roundtrip tests: instead of the two hand-written example
+/// strings above, these generate raw `Vec<String>` (arbitrary unicode text,
+/// unconstrained line count/length/whitespace) and assert each compress path
+/// against what it actually guarantees, shrinking any failure to a minimal
+/// reproducing input.
+#[cfg(test)]
+mod roundtrip_tests {
+    use super::*;
+    use quickcheck::{Arbitrary, Gen, TestResult};
+
+    /// Thin wrapper around `quickcheck`'s own `Vec<String>`/`String` generators
+    /// (arbitrary unicode scalars, arbitrary length), so nothing steers inputs
+    /// away from the empty lines, repeated/leading/trailing whitespace, or
+    /// single-symbol alphabets that the real compress paths have to handle.
+    #[derive(Debug, Clone)]
+    struct Lines(Vec<String>);
+
+    impl Arbitrary for Lines {
+        fn arbitrary(g: &mut Gen) -> Self {
+            Lines(Vec::<String>::arbitrary(g))
+        }
+
+        fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+            Box::new(self.0.shrink().map(Lines))
+        }
+    }
+
+    /// `build_huffman_tree` panics on an empty frequency table (no tokens at
+    /// all across every line); that's a pre-existing, unrelated degenerate
+    /// case, so discard it instead of asserting either way.
+    fn discard_if_no_chars(lines: &[String]) -> bool {
+        lines.iter().all(|line| line.is_empty())
+    }
+
+    fn discard_if_no_words(lines: &[String]) -> bool {
+        lines.iter().all(|line| line.split_ascii_whitespace().next().is_none())
+    }
+
+    #[quickcheck_macros::quickcheck]
+    fn compress_as_chars_roundtrips(lines: Lines) -> TestResult {
+        let Lines(lines) = lines;
+        if discard_if_no_chars(&lines) {
+            return TestResult::discard();
+        }
+
+        let data = compress_as_chars(&lines).unwrap();
+        let res = decompress(data, |x: Vec<char>| x.into_iter().collect()).unwrap();
+        TestResult::from_bool(res == lines)
+    }
+
+    #[quickcheck_macros::quickcheck]
+    fn compress_as_chars_canonical_roundtrips(lines: Lines) -> TestResult {
+        let Lines(lines) = lines;
+        if discard_if_no_chars(&lines) {
+            return TestResult::discard();
+        }
+
+        let data = compress_as_chars_canonical(&lines).unwrap();
+        let res = decompress_canonical(data, |x: Vec<char>| x.into_iter().collect()).unwrap();
+        TestResult::from_bool(res == lines)
+    }
+
+    /// Word mode re-tokenizes with `split_ascii_whitespace` and rejoins with a
+    /// single space, so it is *not* byte-for-byte lossless for lines with
+    /// irregular whitespace (leading/trailing/repeated spaces, tabs). Assert
+    /// against that real, documented behavior rather than lines that dodge it.
+    fn whitespace_normalized(lines: &[String]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.split_ascii_whitespace().collect::<Vec<_>>().join(" "))
+            .collect()
+    }
+
+    #[quickcheck_macros::quickcheck]
+    fn compress_as_words_roundtrips_whitespace_normalized(lines: Lines) -> TestResult {
+        let Lines(lines) = lines;
+        if discard_if_no_words(&lines) {
+            return TestResult::discard();
+        }
+
+        let expected = whitespace_normalized(&lines);
+        let data = compress_as_words(&lines).unwrap();
+        let res = decompress(data, |x: Vec<String>| x.join(" ")).unwrap();
+        TestResult::from_bool(res == expected)
+    }
+
+    #[quickcheck_macros::quickcheck]
+    fn compress_as_words_canonical_roundtrips_whitespace_normalized(lines: Lines) -> TestResult {
+        let Lines(lines) = lines;
+        if discard_if_no_words(&lines) {
+            return TestResult::discard();
+        }
+
+        let expected = whitespace_normalized(&lines);
+        let data = compress_as_words_canonical(&lines).unwrap();
+        let res = decompress_canonical(data, |x: Vec<String>| x.join(" ")).unwrap();
+        TestResult::from_bool(res == expected)
+    }
+
+    /// Covers only the direct `Tree::encode`/`Tree::decode` path (one `BitVec`
+    /// per token) -- it does NOT exercise `compress_as_chars`/`decompress`,
+    /// whose on-disk format is a different, tokenized-per-line encoding.
+    #[quickcheck_macros::quickcheck]
+    fn tree_encode_decode_roundtrips(lines: Lines) -> bool {
+        let Lines(lines) = lines;
+        let tokens: Vec<char> = lines.iter().flat_map(|line| line.chars()).collect();
+        if tokens.is_empty() {
+            return true;
+        }
+
+        let tree = huffman::build_huffman_tree(&huffman::learn_frequencies(&lines));
+        let encoded = tree.encode(&tokens);
+        let decoded = tree.decode(&encoded);
+        decoded == tokens
+    }
+
+    // degenerate one-symbol alphabet through the direct Tree API: the tree is
+    // a lone leaf, which can break decoders that match against a prefix table
+    // instead of walking the tree
+    #[quickcheck_macros::quickcheck]
+    fn single_symbol_alphabet_tree_roundtrips(repeat: u8) -> bool {
+        let tokens = vec!['*'; repeat as usize % 20 + 1];
+
+        let mut freqs = HashMap::new();
+        freqs.insert('*', tokens.len() as i64);
+        let tree = huffman::build_huffman_tree(&freqs);
+
+        let encoded = tree.encode(&tokens);
+        let decoded = tree.decode(&encoded);
+        decoded == tokens
+    }
+
+    // same degenerate one-symbol alphabet, but through the actual
+    // compress_as_chars/decompress on-disk format, which is the path that
+    // originally lost all content (a single-symbol alphabet encodes to a
+    // zero-length-per-symbol code, see `symbol_counts` on `CompressedData`)
+    #[quickcheck_macros::quickcheck]
+    fn single_symbol_alphabet_compress_as_chars_roundtrips(symbol: char, repeat: u8) -> bool {
+        let lines = vec![symbol.to_string().repeat(repeat as usize % 20 + 1)];
+
+        let data = compress_as_chars(&lines).unwrap();
+        let res = decompress(data, |x: Vec<char>| x.into_iter().collect()).unwrap();
+        res == lines
+    }
 }
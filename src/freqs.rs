@@ -5,7 +5,7 @@ pub fn learn_char_frequencies(lines: &Vec<String>) -> HashMap<char, i64> {
     lines
         .par_iter()
         .fold(
-            || HashMap::new(),
+            HashMap::new,
             |mut freqs: HashMap<_, _>, line: &String| {
                 for ch in line.chars() {
                     *freqs.entry(ch).or_insert(0) += 1;
@@ -14,7 +14,7 @@ pub fn learn_char_frequencies(lines: &Vec<String>) -> HashMap<char, i64> {
             },
         )
         .reduce(
-            || HashMap::new(),
+            HashMap::new,
             |mut freqs1, freqs2| {
                 freqs2
                     .into_iter()
@@ -28,7 +28,7 @@ pub fn learn_word_frequencies(lines: &Vec<String>) -> HashMap<String, i64> {
     lines
         .par_iter()
         .fold(
-            || HashMap::new(),
+            HashMap::new,
             |mut freqs: HashMap<_, _>, line: &String| {
                 for word in line.split_ascii_whitespace() {
                     *freqs.entry(word.to_string()).or_insert(0) += 1;
@@ -37,7 +37,7 @@ pub fn learn_word_frequencies(lines: &Vec<String>) -> HashMap<String, i64> {
             },
         )
         .reduce(
-            || HashMap::new(),
+            HashMap::new,
             |mut freqs1, freqs2| {
                 freqs2
                     .into_iter()
@@ -10,7 +10,7 @@ pub fn learn_frequencies(lines: &Vec<String>) -> HashMap<char, i64> {
     lines
         .par_iter()
         .fold(
-            || HashMap::new(),
+            HashMap::new,
             |mut freqs: HashMap<_, _>, line: &String| {
                 for ch in line.chars() {
                     *freqs.entry(ch).or_insert(0) += 1;
@@ -19,7 +19,7 @@ pub fn learn_frequencies(lines: &Vec<String>) -> HashMap<char, i64> {
             },
         )
         .reduce(
-            || HashMap::new(),
+            HashMap::new,
             |mut freqs1, freqs2| {
                 freqs2
                     .into_iter()
@@ -72,42 +72,104 @@ impl<T: Clone> Tree<T> {
             Leaf { .. } => None,
         }
     }
+
+    /// Decodes a bitstream by walking the tree directly: descend `left` on `false`
+    /// / `right` on `true`, and emit+reset to the root on every `Leaf`. A single
+    /// pointer-follow per bit, no per-symbol allocation or hashing.
+    ///
+    /// A lone-leaf tree (single-symbol alphabet) has no bits to descend on, so
+    /// each bit in `bits` is instead treated as one occurrence of that symbol.
+    pub fn decode_bits(&self, bits: &BitVec) -> Vec<T> {
+        if let Leaf { token, .. } = self {
+            return vec![token.clone(); bits.len()];
+        }
+
+        let mut tokens = vec![];
+        let mut cursor = self;
+        for bit in bits {
+            cursor = if bit {
+                cursor.right().unwrap()
+            } else {
+                cursor.left().unwrap()
+            };
+
+            if let Leaf { token, .. } = cursor {
+                tokens.push(token.clone());
+                cursor = self;
+            }
+        }
+
+        tokens
+    }
+
+    /// Like `decode_bits`, but stops after `count` symbols instead of running
+    /// to the end of `bits`. Used when decoding a byte-packed bitstream, whose
+    /// trailing byte may be zero-padded and would otherwise decode as spurious
+    /// extra symbols.
+    pub fn decode_bits_limited(&self, bits: &BitVec, count: usize) -> Vec<T> {
+        if count == 0 {
+            return vec![];
+        }
+
+        if let Leaf { token, .. } = self {
+            return vec![token.clone(); count];
+        }
+
+        let mut tokens = Vec::with_capacity(count);
+        let mut cursor = self;
+        for bit in bits {
+            cursor = if bit {
+                cursor.right().unwrap()
+            } else {
+                cursor.left().unwrap()
+            };
+
+            if let Leaf { token, .. } = cursor {
+                tokens.push(token.clone());
+                if tokens.len() == count {
+                    break;
+                }
+                cursor = self;
+            }
+        }
+
+        tokens
+    }
 }
 
 impl<T: Eq + Clone + Hash> Tree<T> {
-    pub fn to_encoder(&self) -> HashMap<T, BitVec> {
-        let mut encoder = HashMap::new();
+    /// Rebuilds the tree shape from a symbol -> code table, e.g. one deserialized
+    /// from a compressed file's header. `freq` is meaningless for a reconstructed
+    /// tree, so nodes are stamped with `0`; only the shape matters for decoding.
+    pub fn from_code_table(codes: &HashMap<T, BitVec>) -> Tree<T> {
+        let mut root: Option<Box<PartialTree<T>>> = None;
+        for (token, code) in codes {
+            insert_code(&mut root, code, 0, token);
+        }
+
+        finalize_partial_tree(*root.expect("code table must have at least one symbol"))
+    }
 
-        let mut stack = vec![(self, BitVec::new())];
-        while !stack.is_empty() {
-            let (node, path) = stack.pop().unwrap();
+    /// Depth of each symbol's leaf, i.e. the length of its Huffman code, without
+    /// building the codes themselves. Used by the canonical encoding, where only
+    /// the lengths need to be serialized and the codes are replayed from them.
+    pub fn code_lengths(&self) -> HashMap<T, u8> {
+        let mut lengths = HashMap::new();
+
+        let mut stack = vec![(self, 0u8)];
+        while let Some((node, depth)) = stack.pop() {
             match node {
                 Leaf { token, .. } => {
-                    encoder.insert(token.clone(), path.clone());
+                    lengths.insert(token.clone(), depth);
                 }
                 Node { left, right, .. } => {
-                    let mut left_path = path.clone();
-                    left_path.push(false);
-                    stack.push((left, left_path));
-
-                    let mut right_path = path.clone();
-                    right_path.push(true);
-                    stack.push((right, right_path));
+                    stack.push((left, depth + 1));
+                    stack.push((right, depth + 1));
                 }
             }
         }
 
-        encoder
-    }
-
-    pub fn to_decoder(&self, encoder: Option<HashMap<T, BitVec>>) -> HashMap<BitVec, T> {
-        let encoder = encoder.unwrap_or(self.to_encoder());
-
-        let mut decoder = HashMap::new();
-        for (token, prefix) in encoder {
-            decoder.insert(prefix, token);
-        }
-        decoder
+        lengths
     }
 }
 
@@ -145,6 +207,44 @@ pub fn build_huffman_tree<T: Eq + Clone>(freqs: &HashMap<T, i64>) -> Tree<T> {
     heap.pop().unwrap().0
 }
 
+/// Binary trie used while reconstructing a `Tree` from a code table: unlike
+/// `Tree::Node`, a node's children may still be empty mid-insertion.
+enum PartialTree<T> {
+    Leaf(T),
+    Node(Option<Box<PartialTree<T>>>, Option<Box<PartialTree<T>>>),
+}
+
+fn insert_code<T: Clone>(node: &mut Option<Box<PartialTree<T>>>, code: &BitVec, pos: usize, token: &T) {
+    if pos == code.len() {
+        *node = Some(Box::new(PartialTree::Leaf(token.clone())));
+        return;
+    }
+
+    let current = node.get_or_insert_with(|| Box::new(PartialTree::Node(None, None)));
+    if let PartialTree::Node(left, right) = current.as_mut() {
+        if code.get(pos).unwrap() {
+            insert_code(right, code, pos + 1, token);
+        } else {
+            insert_code(left, code, pos + 1, token);
+        }
+    }
+}
+
+fn finalize_partial_tree<T: Clone>(node: PartialTree<T>) -> Tree<T> {
+    match node {
+        PartialTree::Leaf(token) => Leaf { freq: 0, token },
+        PartialTree::Node(left, right) => Node {
+            freq: 0,
+            left: Box::new(finalize_partial_tree(
+                *left.expect("incomplete code table: node is missing its left child"),
+            )),
+            right: Box::new(finalize_partial_tree(
+                *right.expect("incomplete code table: node is missing its right child"),
+            )),
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -223,7 +323,7 @@ mod tests {
         assert!(encoder.get(&'c').unwrap().eq_vec(&[true, false, true]));
         assert!(encoder.get(&'d').unwrap().eq_vec(&[true, false, false]));
 
-        let decoder = tree.to_decoder(Some(encoder.clone()));
+        let decoder = tree.to_decoder(Some(&encoder));
         assert_eq!(decoder.len(), 4);
 
         let mut c_path = BitVec::new();
@@ -232,4 +332,84 @@ mod tests {
         c_path.push(true);
         assert_eq!(decoder.get(&c_path), Some(&'c'));
     }
+
+    #[test]
+    fn decode_bits_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 40);
+        freqs.insert('b', 35);
+        freqs.insert('c', 20);
+        freqs.insert('d', 5);
+
+        let tree = build_huffman_tree(&freqs);
+        let encoder = tree.to_encoder();
+
+        let tokens = ['a', 'a', 'b', 'd', 'c', 'a'];
+        let bits = tokens
+            .iter()
+            .map(|ch| encoder.get(ch).unwrap().clone())
+            .fold(BitVec::new(), |mut acc, code| {
+                acc.extend(code);
+                acc
+            });
+
+        assert_eq!(tree.decode_bits(&bits), tokens);
+    }
+
+    #[test]
+    fn decode_bits_single_symbol_alphabet_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 1);
+
+        let tree = build_huffman_tree(&freqs);
+        assert_eq!(tree, Leaf { freq: 1, token: 'a' });
+
+        let mut bits = BitVec::new();
+        bits.push(false);
+        bits.push(false);
+        bits.push(false);
+        assert_eq!(tree.decode_bits(&bits), vec!['a', 'a', 'a']);
+    }
+
+    #[test]
+    fn decode_bits_limited_ignores_trailing_padding_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 40);
+        freqs.insert('b', 35);
+        freqs.insert('c', 20);
+        freqs.insert('d', 5);
+
+        let tree = build_huffman_tree(&freqs);
+        let encoder = tree.to_encoder();
+
+        let tokens = ['a', 'a', 'b', 'd', 'c'];
+        let mut bits = tokens
+            .iter()
+            .map(|ch| encoder.get(ch).unwrap().clone())
+            .fold(BitVec::new(), |mut acc, code| {
+                acc.extend(code);
+                acc
+            });
+        // pad to a full byte, as a byte-packed bitstream would be
+        while bits.len() % 8 != 0 {
+            bits.push(false);
+        }
+
+        assert_eq!(tree.decode_bits_limited(&bits, tokens.len()), tokens);
+    }
+
+    #[test]
+    fn from_code_table_roundtrips_encoder_test() {
+        let mut freqs = HashMap::new();
+        freqs.insert('a', 40);
+        freqs.insert('b', 35);
+        freqs.insert('c', 20);
+        freqs.insert('d', 5);
+
+        let tree = build_huffman_tree(&freqs);
+        let encoder = tree.to_encoder();
+        let rebuilt = Tree::from_code_table(&encoder);
+
+        assert_eq!(rebuilt.to_encoder(), encoder);
+    }
 }
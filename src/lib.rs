@@ -0,0 +1,5 @@
+pub mod arena;
+pub mod compression;
+pub mod freqs;
+pub mod huffman;
+pub mod stream;
@@ -2,13 +2,11 @@ use std::fs::{self, File};
 use std::io::Write;
 use std::time;
 
-mod compression;
-mod freqs;
-mod huffman;
+use huffman_rs::compression;
 
-const DATA_PATH: &'static str = "data/wikisent2.txt";
-const WORDS_OUT_PATH: &'static str = "data/words.huffman";
-const CHARS_OUT_PATH: &'static str = "data/chars.huffman";
+const DATA_PATH: &str = "data/wikisent2.txt";
+const WORDS_OUT_PATH: &str = "data/words.huffman";
+const CHARS_OUT_PATH: &str = "data/chars.huffman";
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let timer = time::Instant::now();
@@ -21,15 +19,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // words compress & decompress
 
     let timer = time::Instant::now();
-    let compressed = compression::compress(&lines, freqs::learn_word_frequencies, |line| {
-        line.split_ascii_whitespace().map(|token| token.to_string())
-    })?;
+    let compressed = compression::compress_as_words(&lines)?;
     let time = timer.elapsed();
     println!("Compressed as words in {time:?}");
 
     let timer = time::Instant::now();
     let mut out_f = File::create(WORDS_OUT_PATH)?;
-    out_f.write(&compressed)?;
+    out_f.write_all(&compressed)?;
     let time = timer.elapsed();
     println!("Wrote to {WORDS_OUT_PATH} in {time:?}");
 
@@ -49,14 +45,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Chars compress & decompress
     let timer = time::Instant::now();
-    let compressed =
-        compression::compress(&lines, freqs::learn_char_frequencies, |line| line.chars())?;
+    let compressed = compression::compress_as_chars(&lines)?;
     let time = timer.elapsed();
     println!("Compressed as chars in {time:?}");
 
     let timer = time::Instant::now();
     let mut out_f = File::create(CHARS_OUT_PATH)?;
-    out_f.write(&compressed)?;
+    out_f.write_all(&compressed)?;
     let time = timer.elapsed();
     println!("Wrote to {CHARS_OUT_PATH} in {time:?}");
 
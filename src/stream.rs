@@ -0,0 +1,153 @@
+use bit_vec::BitVec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use crate::huffman::{self, Tree};
+
+/// On-disk header for a streamed file: the byte count (so the decoder knows
+/// where the real data ends and zero-padding begins) plus the code table
+/// needed to reconstruct the tree.
+#[derive(Serialize, Deserialize)]
+struct StreamHeader {
+    byte_count: u64,
+    encoder: HashMap<u8, BitVec>,
+}
+
+/// Buffers bits into bytes and flushes full bytes to the underlying writer,
+/// so encoding a stream doesn't need the whole bitstream in memory at once.
+struct BitWriter<W: Write> {
+    output: W,
+    buffer: u8,
+    filled: u8,
+}
+
+impl<W: Write> BitWriter<W> {
+    fn new(output: W) -> Self {
+        BitWriter {
+            output,
+            buffer: 0,
+            filled: 0,
+        }
+    }
+
+    fn write_bits(&mut self, bits: &BitVec) -> std::io::Result<()> {
+        for bit in bits {
+            self.buffer = (self.buffer << 1) | bit as u8;
+            self.filled += 1;
+
+            if self.filled == 8 {
+                self.output.write_all(&[self.buffer])?;
+                self.buffer = 0;
+                self.filled = 0;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pads the last partial byte with zero bits and writes it out.
+    fn finish(mut self) -> std::io::Result<()> {
+        if self.filled > 0 {
+            self.buffer <<= 8 - self.filled;
+            self.output.write_all(&[self.buffer])?;
+        }
+
+        self.output.flush()
+    }
+}
+
+/// Compresses an arbitrary byte stream: learns byte frequencies over `input`,
+/// builds a Huffman tree, writes a header (byte count + code table), then
+/// streams the body through a bit-packing writer.
+///
+/// This is a two-pass approach: `input` is read fully to learn frequencies
+/// before the body is written, so the whole input is held in memory once.
+pub fn compress_reader<R: Read, W: Write>(
+    mut input: R,
+    mut output: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+
+    let mut freqs = HashMap::new();
+    for &byte in &bytes {
+        *freqs.entry(byte).or_insert(0) += 1;
+    }
+
+    let tree = huffman::build_huffman_tree(&freqs);
+    let encoder = tree.to_encoder();
+
+    let header = StreamHeader {
+        byte_count: bytes.len() as u64,
+        encoder,
+    };
+    let header_bytes = rmp_serde::encode::to_vec(&header)?;
+    output.write_all(&(header_bytes.len() as u64).to_le_bytes())?;
+    output.write_all(&header_bytes)?;
+
+    let mut writer = BitWriter::new(&mut output);
+    for byte in &bytes {
+        writer.write_bits(header.encoder.get(byte).unwrap())?;
+    }
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Decompresses a stream written by `compress_reader`: reads the header,
+/// reconstructs the tree, and tree-walks the bitstream emitting decoded bytes.
+pub fn decompress_reader<R: Read, W: Write>(
+    mut input: R,
+    mut output: W,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut len_buf = [0u8; 8];
+    input.read_exact(&mut len_buf)?;
+    let header_len = u64::from_le_bytes(len_buf) as usize;
+
+    let mut header_buf = vec![0u8; header_len];
+    input.read_exact(&mut header_buf)?;
+    let header: StreamHeader = rmp_serde::decode::from_slice(&header_buf)?;
+
+    let tree = Tree::from_code_table(&header.encoder);
+
+    let mut body = Vec::new();
+    input.read_to_end(&mut body)?;
+    let bits = BitVec::from_bytes(&body);
+
+    let decoded = tree.decode_bits_limited(&bits, header.byte_count as usize);
+    output.write_all(&decoded)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compress_decompress_reader_test() {
+        let input = b"the quick brown fox jumps over the lazy dog, again and again.".to_vec();
+
+        let mut compressed = Vec::new();
+        compress_reader(&input[..], &mut compressed).unwrap();
+
+        let mut output = Vec::new();
+        decompress_reader(&compressed[..], &mut output).unwrap();
+
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn compress_decompress_reader_single_byte_alphabet_test() {
+        let input = vec![b'a'; 7];
+
+        let mut compressed = Vec::new();
+        compress_reader(&input[..], &mut compressed).unwrap();
+
+        let mut output = Vec::new();
+        decompress_reader(&compressed[..], &mut output).unwrap();
+
+        assert_eq!(output, input);
+    }
+}